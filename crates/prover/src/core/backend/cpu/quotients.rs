@@ -1,3 +1,5 @@
+use std::ops::Index;
+
 use itertools::{izip, zip_eq};
 use num_traits::{One, Zero};
 
@@ -14,6 +16,24 @@ use crate::core::poly::circle::{CircleDomain, CircleEvaluation, SecureEvaluation
 use crate::core::poly::BitReversedOrder;
 use crate::core::utils::{bit_reverse, bit_reverse_index};
 
+/// Amortized batch inversion of field elements, as a [Backend] capability rather than a bare
+/// [SecureField] operation. Inverts `n` elements at the cost of a single real inversion plus
+/// `~3(n-1)` multiplications (the Montgomery trick): the prefix products `p_0 = a_0`,
+/// `p_i = p_{i-1} * a_i` are computed once, `p_{n-1}` is inverted, and the individual inverses
+/// are recovered walking backwards while updating a running accumulator.
+///
+/// The default implementation simply defers to [SecureField::batch_inverse]. Backends that can
+/// vectorize the prefix/suffix walk (e.g. an AVX/SIMD backend) can override it with a dedicated
+/// implementation instead of falling back to this scalar default, which is what unblocks a
+/// backend-specific [QuotientOps::accumulate_quotients].
+pub trait BatchInverse: Backend {
+    fn batch_inverse(column: &[SecureField], dst: &mut [SecureField]) {
+        SecureField::batch_inverse(column, dst);
+    }
+}
+
+impl BatchInverse for CPUBackend {}
+
 impl QuotientOps for CPUBackend {
     fn accumulate_quotients(
         domain: CircleDomain,
@@ -22,7 +42,7 @@ impl QuotientOps for CPUBackend {
         sample_batches: &[ColumnSampleBatch],
     ) -> SecureEvaluation<Self> {
         let mut values = SecureColumn::zeros(domain.size());
-        let quotient_constants = quotient_constants(sample_batches, random_coeff, domain);
+        let quotient_constants = quotient_constants::<Self>(sample_batches, random_coeff, domain);
 
         // TODO(spapini): bit reverse iterator.
         for row in 0..domain.size() {
@@ -41,13 +61,17 @@ impl QuotientOps for CPUBackend {
     }
 }
 
-pub fn accumulate_row_quotients(
+pub fn accumulate_row_quotients<B: Backend>(
     sample_batches: &[ColumnSampleBatch],
-    columns: &[&CircleEvaluation<CPUBackend, BaseField, BitReversedOrder>],
-    quotient_constants: &QuotientConstants<CPUBackend>,
+    columns: &[&CircleEvaluation<B, BaseField, BitReversedOrder>],
+    quotient_constants: &QuotientConstants<B>,
     row: usize,
     domain_point: CirclePoint<BaseField>,
-) -> SecureField {
+) -> SecureField
+where
+    Col<B, SecureField>: Index<usize, Output = SecureField>,
+    Col<B, BaseField>: Index<usize, Output = BaseField>,
+{
     let mut row_accumulator = SecureField::zero();
     for (sample_batch, line_coeffs, batch_coeff, denominator_inverses) in izip!(
         sample_batches,
@@ -110,43 +134,50 @@ pub fn batch_random_coeffs(
         .collect()
 }
 
-fn denominator_inverses(
+/// Computes the denominator inverses for each sample batch one batch at a time, reusing a pair
+/// of `domain.size()`-length scratch buffers instead of flattening all batches into one giant
+/// allocation upfront. This keeps the scratch memory at `O(domain.size())` rather than
+/// `O(batches * domain.size())`; the returned inverses still take `O(batches * domain.size())`.
+/// Each batch costs one field inversion (one per batch rather than one for all batches), and a
+/// SIMD backend gets the same per-batch iteration structure to vectorize.
+fn denominator_inverses<B: BatchInverse>(
     sample_batches: &[ColumnSampleBatch],
     domain: CircleDomain,
-) -> Vec<Col<CPUBackend, SecureField>> {
-    let mut flat_denominators = Vec::with_capacity(sample_batches.len() * domain.size());
-    for sample_batch in sample_batches {
-        for row in 0..domain.size() {
-            let domain_point = domain.at(row);
-            let denominator = pair_vanishing(
-                sample_batch.point,
-                sample_batch.point.complex_conjugate(),
-                domain_point.into_ef(),
-            );
-            flat_denominators.push(denominator);
-        }
-    }
+) -> Vec<Col<B, SecureField>>
+where
+    Col<B, SecureField>: FromIterator<SecureField>,
+{
+    let mut denominators = vec![SecureField::zero(); domain.size()];
+    let mut inverses = vec![SecureField::zero(); domain.size()];
 
-    let mut flat_denominator_inverses = vec![SecureField::zero(); flat_denominators.len()];
-    SecureField::batch_inverse(&flat_denominators, &mut flat_denominator_inverses);
+    sample_batches
+        .iter()
+        .map(|sample_batch| {
+            let conjugate = sample_batch.point.complex_conjugate();
+            for (row, denominator) in denominators.iter_mut().enumerate() {
+                let domain_point = domain.at(row);
+                *denominator =
+                    pair_vanishing(sample_batch.point, conjugate, domain_point.into_ef());
+            }
 
-    flat_denominator_inverses
-        .chunks_mut(domain.size())
-        .map(|denominator_inverses| {
-            bit_reverse(denominator_inverses);
-            denominator_inverses.to_vec()
+            B::batch_inverse(&denominators, &mut inverses);
+            bit_reverse(&mut inverses);
+            inverses.iter().copied().collect()
         })
         .collect()
 }
 
-pub fn quotient_constants(
+pub fn quotient_constants<B: BatchInverse>(
     sample_batches: &[ColumnSampleBatch],
     random_coeff: SecureField,
     domain: CircleDomain,
-) -> QuotientConstants<CPUBackend> {
+) -> QuotientConstants<B>
+where
+    Col<B, SecureField>: FromIterator<SecureField>,
+{
     let line_coeffs = column_line_coeffs(sample_batches, random_coeff);
     let batch_random_coeffs = batch_random_coeffs(sample_batches, random_coeff);
-    let denominator_inverses = denominator_inverses(sample_batches, domain);
+    let denominator_inverses = denominator_inverses::<B>(sample_batches, domain);
     QuotientConstants {
         line_coeffs,
         batch_random_coeffs,
@@ -154,6 +185,130 @@ pub fn quotient_constants(
     }
 }
 
+/// `z` coincides with one of a [CircleEvaluation]'s domain x-coordinates, so the barycentric
+/// weight of the corresponding node is undefined there (in particular, this always holds when
+/// `z` itself lies on the domain).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PointOnDomain;
+
+impl std::fmt::Display for PointOnDomain {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "evaluation point lies on the domain")
+    }
+}
+
+impl std::error::Error for PointOnDomain {}
+
+/// Evaluates a bit-reversed [CircleEvaluation] at an out-of-domain point `z`, directly from its
+/// values rather than interpolating it to a `CirclePoly` first.
+///
+/// A [CircleEvaluation] over a size-`n` domain represents a function `p -> A(p.x) + p.y * B(p.x)`
+/// for polynomials `A`, `B` of degree `< n/2`, so `f(z)` reduces to two classic barycentric
+/// interpolations over the domain's `n/2` distinct x-coordinates, sharing a single batched
+/// inversion:
+/// - the domain's points come in negated pairs `(x, y)` / `(x, -y)`, which under bit-reversal
+///   land on adjacent storage rows `(2m, 2m + 1)`;
+/// - the barycentric weight `w_m = 1 / prod_{j != m} (x_m - x_j)` is precomputed per
+///   x-coordinate (`m` ranges over pairs, not rows);
+/// - the z-dependent shifts `z.x - x_m`, together with each pair's `2 * y_m` normalizer, are all
+///   batch-inverted in a single pass (no per-node division) and combined with `w_m` and the
+///   pair's two values to accumulate `f(z)`.
+///
+/// # Complexity
+/// The `w_m` computation below is the naive `O(n^2)` nested-product formula, making this
+/// function asymptotically *worse* than `interpolate()` (`O(n log n)`) followed by
+/// `eval_at_point()`. It exists for callers that only hold a [CircleEvaluation] and want to
+/// avoid building a `CirclePoly`, not for performance on large domains: prefer
+/// `interpolate().eval_at_point()` whenever `n` isn't small. A sub-quadratic `w_m` computation
+/// (e.g. via the derivative of the x-coordinates' vanishing polynomial) is a possible follow-up,
+/// but is not implemented here.
+///
+/// # Errors
+/// Returns [PointOnDomain] if `z.x` coincides with one of the domain's x-coordinates.
+pub fn barycentric_eval<B: BatchInverse>(
+    evaluation: &CircleEvaluation<B, BaseField, BitReversedOrder>,
+    z: CirclePoint<SecureField>,
+) -> Result<SecureField, PointOnDomain>
+where
+    Col<B, BaseField>: Index<usize, Output = BaseField>,
+{
+    let domain = evaluation.domain;
+    let log_size = domain.log_size();
+    let half_size = domain.size() / 2;
+
+    // The domain's negated-pair points, one per pair, in storage-row order.
+    let points: Vec<CirclePoint<BaseField>> = (0..half_size)
+        .map(|m| domain.at(bit_reverse_index(2 * m, log_size)))
+        .collect();
+
+    // Classic barycentric weights for the x-coordinates: w_m = 1 / prod_{j != m} (x_m - x_j).
+    let mut products = vec![BaseField::one(); half_size];
+    for (m, product) in products.iter_mut().enumerate() {
+        for (j, other) in points.iter().enumerate() {
+            if j != m {
+                *product *= points[m].x - other.x;
+            }
+        }
+    }
+    let mut weights = vec![BaseField::zero(); half_size];
+    BaseField::batch_inverse(&products, &mut weights);
+
+    // Interleave the z-dependent shifts with each pair's `2 * y_m` normalizer so both are
+    // recovered from a single batched inversion instead of inverting one-at-a-time per node.
+    let mut to_invert = Vec::with_capacity(2 * half_size);
+    for point in &points {
+        let shift = z.x - SecureField::from(point.x);
+        if shift == SecureField::zero() {
+            return Err(PointOnDomain);
+        }
+        to_invert.push(shift);
+        to_invert.push(SecureField::from(point.y + point.y));
+    }
+    let mut inverted = vec![SecureField::zero(); 2 * half_size];
+    B::batch_inverse(&to_invert, &mut inverted);
+
+    let mut numerator = SecureField::zero();
+    let mut denominator = SecureField::zero();
+    for (m, point) in points.iter().enumerate() {
+        let inv_shift = inverted[2 * m];
+        let inv_two_y = inverted[2 * m + 1];
+        let raw_weight = SecureField::from(weights[m]) * inv_shift;
+        denominator += raw_weight;
+
+        let partner = domain.at(bit_reverse_index(2 * m + 1, log_size));
+        debug_assert_eq!(partner.x, point.x);
+        debug_assert_eq!(partner.y, -point.y);
+        debug_assert_ne!(point.y, BaseField::zero());
+
+        let y = SecureField::from(point.y);
+        let v_pos = SecureField::from(evaluation[2 * m]);
+        let v_neg = SecureField::from(evaluation[2 * m + 1]);
+        numerator += raw_weight * (v_pos * (y + z.y) + v_neg * (y - z.y)) * inv_two_y;
+    }
+    Ok(numerator / denominator)
+}
+
+/// Builds a [ColumnSampleBatch] for `point` by evaluating each of `columns` at it via
+/// [barycentric_eval], so the prover never needs to round-trip through coefficient form to
+/// sample an out-of-domain point.
+pub fn column_sample_batch_at_point<B: BatchInverse>(
+    columns: &[&CircleEvaluation<B, BaseField, BitReversedOrder>],
+    point: CirclePoint<SecureField>,
+) -> Result<ColumnSampleBatch, PointOnDomain>
+where
+    Col<B, BaseField>: Index<usize, Output = BaseField>,
+{
+    let columns_and_values = columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| Ok((i, barycentric_eval(column, point)?)))
+        .collect::<Result<_, _>>()?;
+    Ok(ColumnSampleBatch {
+        point,
+        columns_and_values,
+    })
+}
+
 /// Holds the precomputed constant values used in each quotient evaluation.
 pub struct QuotientConstants<B: Backend> {
     /// The line coefficients for each quotient numerator term. For more details see
@@ -168,11 +323,18 @@ pub struct QuotientConstants<B: Backend> {
 
 #[cfg(test)]
 mod tests {
+    use num_traits::Zero;
+
+    use super::{barycentric_eval, denominator_inverses, PointOnDomain};
     use crate::core::backend::cpu::{CPUCircleEvaluation, CPUCirclePoly};
     use crate::core::backend::CPUBackend;
     use crate::core::circle::SECURE_FIELD_CIRCLE_GEN;
+    use crate::core::constraints::pair_vanishing;
+    use crate::core::fields::qm31::SecureField;
+    use crate::core::fields::{ComplexConjugate, FieldExpOps};
     use crate::core::pcs::quotients::{ColumnSampleBatch, QuotientOps};
     use crate::core::poly::circle::CanonicCoset;
+    use crate::core::utils::bit_reverse;
     use crate::{m31, qm31};
 
     #[test]
@@ -197,4 +359,69 @@ mod tests {
             CPUCircleEvaluation::new(eval_domain, quot_eval.columns[0].clone()).interpolate();
         assert!(quot_poly_base_field.is_in_fft_space(LOG_SIZE));
     }
+
+    #[test]
+    fn test_barycentric_eval_matches_eval_at_point() {
+        const LOG_SIZE: u32 = 5;
+        let polynomial = CPUCirclePoly::new((0..1 << LOG_SIZE).map(|i| m31!(i)).collect());
+        let domain = CanonicCoset::new(LOG_SIZE).circle_domain();
+        let eval = polynomial.evaluate(domain);
+        let z = SECURE_FIELD_CIRCLE_GEN;
+
+        let value = barycentric_eval::<CPUBackend>(&eval, z).unwrap();
+
+        assert_eq!(value, polynomial.eval_at_point(z));
+    }
+
+    #[test]
+    fn test_barycentric_eval_rejects_point_on_domain() {
+        const LOG_SIZE: u32 = 5;
+        let polynomial = CPUCirclePoly::new((0..1 << LOG_SIZE).map(|i| m31!(i)).collect());
+        let domain = CanonicCoset::new(LOG_SIZE).circle_domain();
+        let eval = polynomial.evaluate(domain);
+        let on_domain = domain.at(0).into_ef();
+
+        assert_eq!(
+            barycentric_eval::<CPUBackend>(&eval, on_domain),
+            Err(PointOnDomain)
+        );
+    }
+
+    #[test]
+    fn test_denominator_inverses_matches_naive_multi_batch() {
+        const LOG_SIZE: u32 = 4;
+        let domain = CanonicCoset::new(LOG_SIZE).circle_domain();
+        let sample_batches = vec![
+            ColumnSampleBatch {
+                point: SECURE_FIELD_CIRCLE_GEN,
+                columns_and_values: vec![(0, qm31!(1, 2, 3, 4))],
+            },
+            ColumnSampleBatch {
+                point: SECURE_FIELD_CIRCLE_GEN.complex_conjugate(),
+                columns_and_values: vec![(0, qm31!(5, 6, 7, 8)), (1, qm31!(1, 1, 1, 1))],
+            },
+        ];
+
+        let actual = denominator_inverses::<CPUBackend>(&sample_batches, domain);
+
+        // Reference: each batch's denominators inverted directly with `SecureField::batch_inverse`,
+        // freshly allocated per batch, independently of the backend's `BatchInverse`.
+        let expected: Vec<Vec<SecureField>> = sample_batches
+            .iter()
+            .map(|sample_batch| {
+                let conjugate = sample_batch.point.complex_conjugate();
+                let denominators: Vec<SecureField> = (0..domain.size())
+                    .map(|row| {
+                        pair_vanishing(sample_batch.point, conjugate, domain.at(row).into_ef())
+                    })
+                    .collect();
+                let mut inverses = vec![SecureField::zero(); denominators.len()];
+                SecureField::batch_inverse(&denominators, &mut inverses);
+                bit_reverse(&mut inverses);
+                inverses
+            })
+            .collect();
+
+        assert_eq!(actual, expected);
+    }
 }